@@ -0,0 +1,58 @@
+use std::fmt;
+
+/// The error type returned by fallible [`crate::RPNParser`] operations.
+///
+/// Replaces the `panic!`/`eprintln!` calls that used to live inline in
+/// `parse`, so library consumers can recover from bad input instead of
+/// aborting the process.
+#[derive(Debug, PartialEq)]
+pub enum CalcError {
+    /// A division (or modulo) was attempted with a zero divisor.
+    DivideByZero,
+    /// A token wasn't recognized as a number, operator, or keyword.
+    UnknownOperator(String),
+    /// A `@name` lookup referenced a variable that was never stored.
+    UnknownVariable(String),
+    /// An operation needed a value but `self.stack` was empty.
+    EmptyStack,
+    /// The expression ended in a bare number instead of an operator.
+    TrailingOperatorExpected,
+    /// A stack entry couldn't be parsed as a number.
+    NotANumber(String),
+    /// A whole-number operation (bitwise, modulo, floor-division, shift, factorial) got an
+    /// operand with a fractional part, or, for factorial, a negative operand.
+    NotAnInteger(String),
+    /// A shift amount was negative or at least as wide as the value being shifted.
+    InvalidShiftAmount(i64),
+    /// An arithmetic operation produced a value too large to fit in the result type.
+    Overflow,
+    /// An infix expression had unbalanced parentheses.
+    MismatchedParentheses,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::DivideByZero => write!(f, "Divide by zero"),
+            CalcError::UnknownOperator(token) => {
+                write!(f, "Unknown operator or number: `{}`", token)
+            }
+            CalcError::UnknownVariable(name) => write!(f, "Unknown variable: `{}`", name),
+            CalcError::EmptyStack => write!(f, "Stack is empty"),
+            CalcError::TrailingOperatorExpected => {
+                write!(f, "Last item needs to be an operator")
+            }
+            CalcError::NotANumber(token) => write!(f, "Could not parse number: `{}`", token),
+            CalcError::NotAnInteger(token) => {
+                write!(f, "Expected a whole number, got: `{}`", token)
+            }
+            CalcError::InvalidShiftAmount(amount) => {
+                write!(f, "Shift amount must be between 0 and 63, got: `{}`", amount)
+            }
+            CalcError::Overflow => write!(f, "Result too large to represent"),
+            CalcError::MismatchedParentheses => write!(f, "Mismatched parentheses"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
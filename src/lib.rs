@@ -1,295 +1,879 @@
-///! # RPN Calculator
-///!
-///! This is a small program that parses a Reverse Polish Notation Equation
-///! and returns the result.
-///!
-///! This program is based off https://gist.github.com/wd5gnr/68d067c3c42a2e0e9a27b083e01f7080#file-rpn-py
-///! by https://github.com/wd5gnr
-////////////////////////////////////////////////////////////////////////////////
-use anyhow::{Context, Result};
-use std::collections::HashMap;
-
-/// Parser Struct for holding the stack array and variable hashmap
-#[derive(Default)]
-pub struct RPNParser {
-    /// The main stack. Numbers and operators go here.
-    pub stack: Vec<String>,
-    /// A Hashmap used to hold temporary variables for advanced processing.
-    pub vars: HashMap<String, String>,
-}
-
-impl RPNParser {
-    /// Returns a instance of `Parser` with initialized values.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # use rpn_calculator::RPNParser;
-    /// let mut calc = RPNParser::new();
-    /// ```
-    pub fn new() -> Self {
-        Default::default()
-    }
-
-    /// Parses a Reverse Polish Notation Equation and calculates the result.
-    /// # Arguments
-    ///
-    /// * `expression` - A string slice that holds the equation to calculate.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use rpn_calculator::RPNParser;
-    ///
-    /// let mut calc = RPNParser::new();
-    ///
-    /// calc.parse("5 2 + -3 - 10 +").unwrap(); // .parse() returns a Result
-    ///
-    /// let result = calc.peek().unwrap(); // .peek() returns a Result
-    ///
-    /// assert_eq!(result, "20")
-    /// ```
-    pub fn parse(&mut self, expression: &str) -> Result<()> {
-        // Split the `expression` string slice into an array, delimited
-        // by whitespace.
-        let tokens: Vec<String> = expression
-            .split_whitespace()
-            .map(|s| s.to_string()) // We need our tokens to be `String`
-            .collect();
-        if tokens.len() == 0 {
-            println!("Nothing to parse!")
-        }
-
-        for token in &tokens {
-            match token.parse::<isize>() {
-                // The token is a number, so put it on the stack.
-                Ok(value) => {
-                    let last = tokens.last().unwrap().to_owned();
-                    if last == value.to_string() {
-                        eprintln!("Last item needs to be an operator!")
-                    } else {
-                        self.push(value.to_string())
-                    }
-                }
-                // The token is not a number, so it's either
-                // an operation, or invalid.
-                Err(_) => match token.to_lowercase().as_str() {
-                    "x" => self.exchange()?,
-                    "?" => self.stack_dump(),
-                    "&" => self.var_dump(),
-                    "+" => self.add()?,
-                    "-" => self.subtract()?,
-                    "*" => self.multiply()?,
-                    "/" => self.divide()?,
-                    "^" => self.exponent()?,
-                    _ => {
-                        // Dealing with the advanced variable operations...
-                        if token.chars().nth(0) == Some('!') {
-                            // We're storing the number at the top of the stack
-                            // in a key/value HashMap: `self.vars`
-                            // self.vars[key] is the variable name without the '!'
-                            // self.vars[key][value] is the number we're storing.
-                            let val = self.peek().unwrap();
-                            self.vars.insert(token.as_str()[1..].to_string(), val);
-                        } else if token.chars().nth(0) == Some('@') {
-                            // We're retrieving the number stored in the variable
-                            // '@variable'.
-                            let result = token.as_str()[1..].to_string();
-                            if !result.is_empty() {
-                                // Retrieve the number stored in the variable
-                                // '@variable'.
-                                let entry = self.vars.get(&result).unwrap().to_owned();
-                                self.push(entry)
-                            } else {
-                                panic!("Unknown variable: `{}`", token)
-                            }
-                        } else {
-                            // Invalid token, so we panic!
-                            panic!("Unknown operator or number: `{}`", token)
-                        }
-                    }
-                },
-            };
-        }
-        Ok(())
-    }
-
-    ///
-    /// Inserts a value at the top of `self.stack`.
-    ///
-    pub fn push(&mut self, value: String) {
-        self.stack.push(value)
-    }
-
-    ///
-    /// Removes the first entry from `self.stack` and returns it.
-    ///
-    pub fn pop(&mut self) -> Result<String> {
-        let result = self.stack.pop().context("Stack is empty!")?;
-        Ok(result)
-    }
-
-    ///
-    /// Returns the value at the top of `self.stack` **without** removing it.
-    ///
-    pub fn peek(&mut self) -> Result<String> {
-        let result = self.stack.last().context("Stack is empty!")?.to_string();
-        Ok(result)
-    }
-
-    /// Clears the parser memory.
-    pub fn clear(&mut self) {
-        self.stack.clear();
-        self.vars.clear();
-    }
-
-    /// Adds the first two values on `self.stack` and
-    /// pushes the sum to the top of `self.stack`.
-    pub fn add(&mut self) -> Result<()> {
-        let (x, y) = self.retrieve_stack_values()?;
-        let result = x + y;
-        self.push(result.to_string());
-        Ok(())
-    }
-
-    /// Subtracts the first two values on `self.stack` and
-    /// pushes the difference to the top of `self.stack`.
-    ///
-    /// The equation here is `self.stack[1] - self.stack[0]` due the stack ordering.
-    pub fn subtract(&mut self) -> Result<()> {
-        let (x, y) = self.retrieve_stack_values()?;
-        let result = y - x;
-        Ok(self.push(result.to_string()))
-    }
-
-    /// Multiplies the first two values on `self.stack` and
-    /// pushes the result to the top of `self.stack`.
-    pub fn multiply(&mut self) -> Result<()> {
-        let (x, y) = self.retrieve_stack_values()?;
-        let result = x * y;
-        Ok(self.push(result.to_string()))
-    }
-
-    /// Divides the first two values on `self.stack` and
-    /// pushes the result to the top of `self.stack`.
-    ///
-    /// The equation here is `self.stack[1] / self.stack[0]` due the stack ordering.
-    pub fn divide(&mut self) -> Result<()> {
-        let (x, y) = self.retrieve_stack_values()?;
-        let result = y / x;
-        Ok(self.push(result.to_string()))
-    }
-
-    /// Raises a base value to a specified power.
-    ///
-    /// The `base_val` is the first value off `self.stack`.
-    /// The `power` is the second value off `self.stack`.
-    pub fn exponent(&mut self) -> Result<()> {
-        let base_val: isize = self.pop()?.parse()?;
-        let power: u32 = self.pop()?.parse()?;
-        let result = base_val.pow(power);
-        Ok(self.push(result.to_string()))
-    }
-
-    /// Exchanges the position of the first two values on `self.stack`.
-    ///
-    /// If `self.stack` had `10, 2`, then `self.exchange()` would change this
-    /// to `2, 10`
-    ///
-    /// Will panic if `self.stack` is empty.    
-    pub fn exchange(&mut self) -> Result<()> {
-        let t = self.pop()?;
-        let t1 = self.pop()?;
-        self.push(t);
-        self.push(t1);
-        Ok(())
-    }
-
-    ///
-    /// Utility function.
-    ///
-    /// Retrieves the first and second values off the stack and
-    /// returns them as `isize`.
-    fn retrieve_stack_values(&mut self) -> Result<(isize, isize)> {
-        let x: isize = self.pop()?.parse()?;
-        let y: isize = self.pop()?.parse()?;
-        Ok((x, y))
-    }
-
-    /// Diagnostic function. Dumps the contents of `self.stack`.    
-    pub fn stack_dump(&self) {
-        if self.stack.len() > 0 {
-            print!("STACK:\n");
-            for item in self.stack.to_owned() {
-                println!("\tStack = {}", item);
-            }
-            print!("\n");
-        }
-    }
-
-    /// Diagnostic function. Dumps the contents of `self.vars`.
-    pub fn var_dump(&self) {
-        if self.stack.len() > 0 {
-            print!("TEMP VARS\n");
-            for (key, value) in self.vars.to_owned() {
-                println!("\tKey = {} = {}", key, value);
-            }
-            print!("\n");
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn basic_notation() {
-        let mut calc = RPNParser::new();
-        calc.parse("5 2 + -3 - 10 +").unwrap();
-        // (5+2) - (-3) + 10 = 20
-        let result = calc.peek().unwrap();
-        assert_eq!(result, "20")
-    }
-
-    #[test]
-    fn exponent_notation() {
-        let mut calc = RPNParser::new();
-        calc.parse("5 5 ^ 125 - 30 /").unwrap();
-        // (((5^5) - 125) / 30) = 100
-        let result = calc.peek().unwrap();
-        assert_eq!(result, "100")
-    }
-
-    #[test]
-    fn manual_addition() {
-        let mut calc = RPNParser::new();
-        calc.push("10".to_string()); // Push '10' to the top of the stack.
-        assert_eq!(calc.peek().unwrap(), "10");
-        calc.push("99".to_string());
-        assert_eq!(calc.peek().unwrap(), "99"); // Push '99' to the top of the stack.
-        calc.add().unwrap();
-        // 99 + 10 = 109 ('99' is at the top of the stack, followed by '10')
-        assert_eq!(calc.peek().unwrap(), "109")
-    }
-
-    #[test]
-    fn manual_power_raising() {
-        let mut calc = RPNParser::new();
-        calc.push("5".to_string()); // Push 5 to the top of the stack
-        calc.push("5".to_string()); // Push another 5 to the top of the stack
-        calc.exponent().unwrap();
-        // 5^5 = 3125
-        assert_eq!(calc.peek().unwrap(), "3125")
-    }
-
-    #[test]
-    fn variable_testing() {
-        let mut calc = RPNParser::new();
-        calc.parse("50 20 + !temp").unwrap(); // 50 + 20 = 70 <-- Store result in temporary variable named 'temp'.
-        calc.pop().unwrap(); // Pops '70' off the stack; which should now be empty.
-        calc.parse("2 @temp *").unwrap(); // Retrieve 'temp' var, which should be '70'.
-                                          // 2 * `temp`(70) = 140.
-        assert_eq!(calc.peek().unwrap(), "140")
-    }
-}
+///! # RPN Calculator
+///!
+///! This is a small program that parses a Reverse Polish Notation Equation
+///! and returns the result.
+///!
+///! This program is based off https://gist.github.com/wd5gnr/68d067c3c42a2e0e9a27b083e01f7080#file-rpn-py
+///! by https://github.com/wd5gnr
+////////////////////////////////////////////////////////////////////////////////
+use std::collections::HashMap;
+
+mod error;
+mod num;
+pub use error::CalcError;
+pub use num::Num;
+
+/// A convenience alias for `Result`s that fail with a [`CalcError`].
+pub type Result<T> = std::result::Result<T, CalcError>;
+
+/// Parser Struct for holding the stack array and variable hashmap
+#[derive(Default)]
+pub struct RPNParser {
+    /// The main stack. Numbers and operators go here.
+    pub stack: Vec<String>,
+    /// A Hashmap used to hold temporary variables for advanced processing.
+    pub vars: HashMap<String, String>,
+}
+
+impl RPNParser {
+    /// Returns a instance of `Parser` with initialized values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rpn_calculator::RPNParser;
+    /// let mut calc = RPNParser::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Parses a Reverse Polish Notation Equation and calculates the result.
+    /// # Arguments
+    ///
+    /// * `expression` - A string slice that holds the equation to calculate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rpn_calculator::RPNParser;
+    ///
+    /// let mut calc = RPNParser::new();
+    ///
+    /// calc.parse("5 2 + -3 - 10 +").unwrap(); // .parse() returns a Result
+    ///
+    /// let result = calc.peek().unwrap(); // .peek() returns a Result
+    ///
+    /// assert_eq!(result, "20")
+    /// ```
+    pub fn parse(&mut self, expression: &str) -> Result<()> {
+        // Split the `expression` string slice into an array, delimited
+        // by whitespace.
+        let tokens: Vec<String> = expression
+            .split_whitespace()
+            .map(|s| s.to_string()) // We need our tokens to be `String`
+            .collect();
+        if tokens.len() == 0 {
+            println!("Nothing to parse!")
+        }
+
+        for (i, token) in tokens.iter().enumerate() {
+            match Num::parse(token) {
+                // The token is a number, so put it on the stack.
+                Ok(value) => {
+                    // A single bare number (e.g. typed straight into the REPL) is
+                    // valid input; only a *trailing* number in a longer expression
+                    // means the expression is missing its final operator.
+                    let is_trailing = tokens.len() > 1 && i == tokens.len() - 1;
+                    if is_trailing {
+                        return Err(CalcError::TrailingOperatorExpected);
+                    } else {
+                        self.push(value.to_string())
+                    }
+                }
+                // The token is not a number, so it's either
+                // an operation, or invalid.
+                Err(_) => match token.to_lowercase().as_str() {
+                    "x" => self.exchange()?,
+                    "?" => self.stack_dump(),
+                    "&" => self.var_dump(),
+                    "+" => self.add()?,
+                    "-" => self.subtract()?,
+                    "*" => self.multiply()?,
+                    "/" => self.divide()?,
+                    "^" => self.exponent()?,
+                    "%" => self.modulo()?,
+                    "//" => self.floor_divide()?,
+                    "and" => self.bitand()?,
+                    "or" => self.bitor()?,
+                    "xor" => self.bitxor()?,
+                    "shl" => self.shift_left()?,
+                    "shr" => self.shift_right()?,
+                    "pi" => self.push(Num::Float(std::f64::consts::PI).to_string()),
+                    "e" => self.push(Num::Float(std::f64::consts::E).to_string()),
+                    "sqrt" => self.sqrt()?,
+                    "abs" => self.abs()?,
+                    "neg" => self.neg()?,
+                    "ln" => self.ln()?,
+                    "sin" => self.sin()?,
+                    "cos" => self.cos()?,
+                    "tan" => self.tan()?,
+                    // A standalone `!` is factorial; `!name` (handled below) stores a variable.
+                    "!" => self.factorial()?,
+                    "dup" => self.dup()?,
+                    "drop" => self.drop()?,
+                    "over" => self.over()?,
+                    "roll" | "rot" => self.rot()?,
+                    _ => {
+                        // Dealing with the advanced variable operations...
+                        if token.chars().nth(0) == Some('!') {
+                            // We're storing the number at the top of the stack
+                            // in a key/value HashMap: `self.vars`
+                            // self.vars[key] is the variable name without the '!'
+                            // self.vars[key][value] is the number we're storing.
+                            let val = self.peek()?;
+                            self.vars.insert(token.as_str()[1..].to_string(), val);
+                        } else if token.chars().nth(0) == Some('@') {
+                            // We're retrieving the number stored in the variable
+                            // '@variable'.
+                            let result = token.as_str()[1..].to_string();
+                            if !result.is_empty() {
+                                // Retrieve the number stored in the variable
+                                // '@variable'.
+                                let entry = self
+                                    .vars
+                                    .get(&result)
+                                    .ok_or_else(|| CalcError::UnknownVariable(result.clone()))?
+                                    .to_owned();
+                                self.push(entry)
+                            } else {
+                                return Err(CalcError::UnknownVariable(token.to_string()));
+                            }
+                        } else {
+                            return Err(CalcError::UnknownOperator(token.to_string()));
+                        }
+                    }
+                },
+            };
+        }
+        Ok(())
+    }
+
+    /// Parses an infix expression (e.g. `5 + 2 * (3 - 1)`) by rewriting it to
+    /// an RPN token stream using the shunting-yard algorithm, then running
+    /// the result through [`RPNParser::parse`].
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - A string slice holding the infix equation to calculate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rpn_calculator::RPNParser;
+    ///
+    /// let mut calc = RPNParser::new();
+    ///
+    /// calc.parse_infix("5 + 2 * (3 - 1)").unwrap();
+    ///
+    /// let result = calc.peek().unwrap();
+    ///
+    /// assert_eq!(result, "9")
+    /// ```
+    pub fn parse_infix(&mut self, expression: &str) -> Result<()> {
+        let tokens = Self::tokenize_infix(expression);
+        let postfix = Self::to_postfix(&tokens)?;
+        self.parse(&postfix.join(" "))
+    }
+
+    /// Splits an infix expression into tokens, so that operators and
+    /// parentheses don't need to be whitespace-separated by the caller.
+    fn tokenize_infix(expression: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut chars = expression.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c.is_ascii_digit() || c == '.' {
+                let mut num = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' {
+                        num.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(num);
+            } else {
+                tokens.push(c.to_string());
+                chars.next();
+            }
+        }
+        tokens
+    }
+
+    /// Returns the precedence of a supported infix operator. Higher binds tighter.
+    /// `u-` is the internal marker for unary minus; it binds tighter than everything.
+    fn precedence(op: &str) -> u8 {
+        match op {
+            "u-" => 4,
+            "^" => 3,
+            "*" | "/" => 2,
+            "+" | "-" => 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns whether a supported infix operator is right-associative.
+    /// `^` and unary minus are; the rest are left-associative.
+    fn is_right_associative(op: &str) -> bool {
+        op == "^" || op == "u-"
+    }
+
+    /// Returns whether a `-` token at this point in the token stream is unary
+    /// (negation) rather than binary (subtraction): it is if it's the first
+    /// token, or immediately follows another operator, a unary minus, or `(`.
+    fn is_unary_position(prev: Option<&str>) -> bool {
+        match prev {
+            None => true,
+            Some(p) => matches!(p, "(" | "+" | "-" | "*" | "/" | "^" | "u-"),
+        }
+    }
+
+    /// Maps an internal operator-stack token to the RPN token the stack
+    /// machine understands (`u-` becomes the existing `neg` unary word).
+    fn op_to_rpn(op: &str) -> String {
+        if op == "u-" {
+            "neg".to_string()
+        } else {
+            op.to_string()
+        }
+    }
+
+    /// Rewrites a sequence of infix tokens into RPN order using the
+    /// shunting-yard algorithm: numbers go straight to the output queue,
+    /// operators are pushed to an operator stack (popping any operator of
+    /// greater precedence, or equal precedence if left-associative), and
+    /// parentheses direct which operators get popped and discarded. A `-`
+    /// that appears where an operand is expected is treated as unary minus.
+    fn to_postfix(tokens: &[String]) -> Result<Vec<String>> {
+        let mut output = Vec::new();
+        let mut ops: Vec<&str> = Vec::new();
+        let mut prev: Option<&str> = None;
+        for token in tokens {
+            if token.parse::<f64>().is_ok() {
+                output.push(token.clone());
+                prev = Some("0");
+            } else if token == "-" && Self::is_unary_position(prev) {
+                // Unary minus is a prefix operator: it has no operand yet, so
+                // nothing queued ahead of it on the stack is waiting to be
+                // flushed. Just push it; it (and any unary minuses stacked on
+                // top of it) get popped once its operand is emitted, by the
+                // normal flush below or when a later binary operator arrives.
+                ops.push("u-");
+                prev = Some("u-");
+            } else if matches!(token.as_str(), "+" | "-" | "*" | "/" | "^") {
+                while let Some(&top) = ops.last() {
+                    if top != "(" && (Self::precedence(top) > Self::precedence(token)
+                        || (Self::precedence(top) == Self::precedence(token)
+                            && !Self::is_right_associative(token)))
+                    {
+                        output.push(Self::op_to_rpn(ops.pop().unwrap()));
+                    } else {
+                        break;
+                    }
+                }
+                ops.push(token.as_str());
+                prev = Some(token.as_str());
+            } else if token == "(" {
+                ops.push("(");
+                prev = Some("(");
+            } else if token == ")" {
+                while let Some(&top) = ops.last() {
+                    if top == "(" {
+                        break;
+                    }
+                    output.push(Self::op_to_rpn(ops.pop().unwrap()));
+                }
+                ops.pop().ok_or(CalcError::MismatchedParentheses)?;
+                // A parenthesized group behaves like an operand from here on.
+                prev = Some("0");
+            } else {
+                return Err(CalcError::UnknownOperator(token.to_string()));
+            }
+        }
+        while let Some(op) = ops.pop() {
+            if op == "(" {
+                return Err(CalcError::MismatchedParentheses);
+            }
+            output.push(Self::op_to_rpn(op));
+        }
+        Ok(output)
+    }
+
+    ///
+    /// Inserts a value at the top of `self.stack`.
+    ///
+    pub fn push(&mut self, value: String) {
+        self.stack.push(value)
+    }
+
+    ///
+    /// Removes the first entry from `self.stack` and returns it.
+    ///
+    pub fn pop(&mut self) -> Result<String> {
+        self.stack.pop().ok_or(CalcError::EmptyStack)
+    }
+
+    ///
+    /// Returns the value at the top of `self.stack` **without** removing it.
+    ///
+    pub fn peek(&mut self) -> Result<String> {
+        self.stack
+            .last()
+            .map(|v| v.to_string())
+            .ok_or(CalcError::EmptyStack)
+    }
+
+    /// Clears the parser memory.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.vars.clear();
+    }
+
+    /// Adds the first two values on `self.stack` and
+    /// pushes the sum to the top of `self.stack`.
+    pub fn add(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = x + y;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Subtracts the first two values on `self.stack` and
+    /// pushes the difference to the top of `self.stack`.
+    ///
+    /// The equation here is `self.stack[1] - self.stack[0]` due the stack ordering.
+    pub fn subtract(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = y.subtract(x);
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Multiplies the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    pub fn multiply(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = x.multiply(y);
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Divides the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    ///
+    /// The equation here is `self.stack[1] / self.stack[0]` due the stack ordering.
+    pub fn divide(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = y.divide(x)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Raises the first-pushed value on `self.stack` to the power of the second.
+    ///
+    /// The equation here is `self.stack[1] ^ self.stack[0]` due the stack ordering.
+    pub fn exponent(&mut self) -> Result<()> {
+        let (power, base_val) = self.retrieve_stack_values()?;
+        let result = base_val.exponent(power);
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, takes its square root, and pushes the result.
+    pub fn sqrt(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.sqrt().to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, takes its absolute value, and pushes the result.
+    pub fn abs(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.abs().to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, negates it, and pushes the result.
+    pub fn neg(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push((-v).to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, takes its natural logarithm, and pushes the result.
+    pub fn ln(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.ln().to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, takes its sine (in radians), and pushes the result.
+    pub fn sin(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.sin().to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, takes its cosine (in radians), and pushes the result.
+    pub fn cos(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.cos().to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, takes its tangent (in radians), and pushes the result.
+    pub fn tan(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.tan().to_string());
+        Ok(())
+    }
+
+    /// Pops the top of `self.stack`, computes its factorial, and pushes the result.
+    ///
+    /// Returns [`CalcError::NotAnInteger`] if the value isn't a non-negative whole number.
+    pub fn factorial(&mut self) -> Result<()> {
+        let v = Num::parse(&self.pop()?)?;
+        self.push(v.factorial()?.to_string());
+        Ok(())
+    }
+
+    /// Computes the remainder of the first two values on `self.stack` and
+    /// pushes it to the top of `self.stack`.
+    ///
+    /// The equation here is `self.stack[1] % self.stack[0]` due the stack ordering.
+    pub fn modulo(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = y.modulo(x)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Integer floor-divides the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    ///
+    /// The equation here is `self.stack[1] // self.stack[0]` due the stack ordering.
+    pub fn floor_divide(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = y.floor_divide(x)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Bitwise-ANDs the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    pub fn bitand(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = (x & y)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Bitwise-ORs the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    pub fn bitor(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = (x | y)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Bitwise-XORs the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    pub fn bitxor(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = (x ^ y)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Left-shifts the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    ///
+    /// The equation here is `self.stack[1] << self.stack[0]` due the stack ordering.
+    pub fn shift_left(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = y.shift_left(x)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Right-shifts the first two values on `self.stack` and
+    /// pushes the result to the top of `self.stack`.
+    ///
+    /// The equation here is `self.stack[1] >> self.stack[0]` due the stack ordering.
+    pub fn shift_right(&mut self) -> Result<()> {
+        let (x, y) = self.retrieve_stack_values()?;
+        let result = y.shift_right(x)?;
+        self.push(result.to_string());
+        Ok(())
+    }
+
+    /// Pushes a copy of the top of `self.stack`.
+    ///
+    /// Returns [`CalcError::EmptyStack`] if `self.stack` is empty.
+    pub fn dup(&mut self) -> Result<()> {
+        let t = self.peek()?;
+        self.push(t);
+        Ok(())
+    }
+
+    /// Discards the top of `self.stack`.
+    ///
+    /// Returns [`CalcError::EmptyStack`] if `self.stack` is empty.
+    pub fn drop(&mut self) -> Result<()> {
+        self.pop()?;
+        Ok(())
+    }
+
+    /// Pushes a copy of the second-from-top value on `self.stack` to the top.
+    ///
+    /// If `self.stack` had `10, 2`, then `self.over()` would change this to `10, 2, 10`.
+    ///
+    /// Returns [`CalcError::EmptyStack`] if `self.stack` doesn't hold two values.
+    pub fn over(&mut self) -> Result<()> {
+        if self.stack.len() < 2 {
+            return Err(CalcError::EmptyStack);
+        }
+        let t1 = self.stack[self.stack.len() - 2].clone();
+        self.push(t1);
+        Ok(())
+    }
+
+    /// Rotates the top three values on `self.stack`, bringing the third-from-top
+    /// value to the top.
+    ///
+    /// If `self.stack` had `1, 2, 3`, then `self.rot()` would change this to `2, 3, 1`.
+    ///
+    /// Returns [`CalcError::EmptyStack`] if `self.stack` doesn't hold three values.
+    pub fn rot(&mut self) -> Result<()> {
+        if self.stack.len() < 3 {
+            return Err(CalcError::EmptyStack);
+        }
+        let first = self.pop().unwrap();
+        let second = self.pop().unwrap();
+        let third = self.pop().unwrap();
+        self.push(second);
+        self.push(first);
+        self.push(third);
+        Ok(())
+    }
+
+    /// Exchanges the position of the first two values on `self.stack`.
+    ///
+    /// If `self.stack` had `10, 2`, then `self.exchange()` would change this
+    /// to `2, 10`
+    ///
+    /// Returns [`CalcError::EmptyStack`] if `self.stack` doesn't hold two values.
+    pub fn exchange(&mut self) -> Result<()> {
+        let t = self.pop()?;
+        let t1 = self.pop()?;
+        self.push(t);
+        self.push(t1);
+        Ok(())
+    }
+
+    ///
+    /// Utility function.
+    ///
+    /// Retrieves the first and second values off the stack and
+    /// returns them as [`Num`].
+    fn retrieve_stack_values(&mut self) -> Result<(Num, Num)> {
+        let x = Num::parse(&self.pop()?)?;
+        let y = Num::parse(&self.pop()?)?;
+        Ok((x, y))
+    }
+
+    /// Diagnostic function. Dumps the contents of `self.stack`.
+    pub fn stack_dump(&self) {
+        if self.stack.len() > 0 {
+            print!("STACK:\n");
+            for item in self.stack.to_owned() {
+                println!("\tStack = {}", item);
+            }
+            print!("\n");
+        }
+    }
+
+    /// Diagnostic function. Dumps the contents of `self.vars`.
+    pub fn var_dump(&self) {
+        if self.vars.len() > 0 {
+            print!("TEMP VARS\n");
+            for (key, value) in self.vars.to_owned() {
+                println!("\tKey = {} = {}", key, value);
+            }
+            print!("\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_notation() {
+        let mut calc = RPNParser::new();
+        calc.parse("5 2 + -3 - 10 +").unwrap();
+        // (5+2) - (-3) + 10 = 20
+        let result = calc.peek().unwrap();
+        assert_eq!(result, "20")
+    }
+
+    #[test]
+    fn exponent_notation() {
+        let mut calc = RPNParser::new();
+        calc.parse("5 5 ^ 125 - 30 /").unwrap();
+        // (((5^5) - 125) / 30) = 100
+        let result = calc.peek().unwrap();
+        assert_eq!(result, "100")
+    }
+
+    #[test]
+    fn exponent_operand_order_matches_rpn_convention() {
+        let mut calc = RPNParser::new();
+        calc.parse("2 3 ^").unwrap();
+        // `a b ^` means `a^b`, so `2 3 ^` is 2^3 = 8, not 3^2.
+        assert_eq!(calc.peek().unwrap(), "8");
+    }
+
+    #[test]
+    fn manual_addition() {
+        let mut calc = RPNParser::new();
+        calc.push("10".to_string()); // Push '10' to the top of the stack.
+        assert_eq!(calc.peek().unwrap(), "10");
+        calc.push("99".to_string());
+        assert_eq!(calc.peek().unwrap(), "99"); // Push '99' to the top of the stack.
+        calc.add().unwrap();
+        // 99 + 10 = 109 ('99' is at the top of the stack, followed by '10')
+        assert_eq!(calc.peek().unwrap(), "109")
+    }
+
+    #[test]
+    fn manual_power_raising() {
+        let mut calc = RPNParser::new();
+        calc.push("5".to_string()); // Push 5 to the top of the stack
+        calc.push("5".to_string()); // Push another 5 to the top of the stack
+        calc.exponent().unwrap();
+        // 5^5 = 3125
+        assert_eq!(calc.peek().unwrap(), "3125")
+    }
+
+    #[test]
+    fn variable_testing() {
+        let mut calc = RPNParser::new();
+        calc.parse("50 20 + !temp").unwrap(); // 50 + 20 = 70 <-- Store result in temporary variable named 'temp'.
+        calc.pop().unwrap(); // Pops '70' off the stack; which should now be empty.
+        calc.parse("2 @temp *").unwrap(); // Retrieve 'temp' var, which should be '70'.
+                                          // 2 * `temp`(70) = 140.
+        assert_eq!(calc.peek().unwrap(), "140")
+    }
+
+    #[test]
+    fn fractional_division_promotes_to_float() {
+        let mut calc = RPNParser::new();
+        calc.parse("10 3 /").unwrap();
+        assert_eq!(calc.peek().unwrap(), "3.3333333333333335")
+    }
+
+    #[test]
+    fn mixed_numeric_expression() {
+        let mut calc = RPNParser::new();
+        calc.parse("0.5 2 ^").unwrap();
+        // 0.5^2 = 0.25
+        assert_eq!(calc.peek().unwrap(), "0.25")
+    }
+
+    #[test]
+    fn exact_division_stays_integral() {
+        let mut calc = RPNParser::new();
+        calc.parse("10 5 /").unwrap();
+        assert_eq!(calc.peek().unwrap(), "2")
+    }
+
+    #[test]
+    fn modulo_and_floor_divide() {
+        let mut calc = RPNParser::new();
+        calc.parse("17 5 %").unwrap();
+        assert_eq!(calc.peek().unwrap(), "2");
+
+        let mut calc = RPNParser::new();
+        calc.parse("17 5 //").unwrap();
+        assert_eq!(calc.peek().unwrap(), "3");
+    }
+
+    #[test]
+    fn bitwise_operators() {
+        let mut calc = RPNParser::new();
+        calc.parse("12 10 and").unwrap();
+        assert_eq!(calc.peek().unwrap(), "8");
+
+        let mut calc = RPNParser::new();
+        calc.parse("12 10 or").unwrap();
+        assert_eq!(calc.peek().unwrap(), "14");
+
+        let mut calc = RPNParser::new();
+        calc.parse("12 10 xor").unwrap();
+        assert_eq!(calc.peek().unwrap(), "6");
+
+        let mut calc = RPNParser::new();
+        calc.parse("1 4 shl").unwrap();
+        assert_eq!(calc.peek().unwrap(), "16");
+
+        let mut calc = RPNParser::new();
+        calc.parse("16 4 shr").unwrap();
+        assert_eq!(calc.peek().unwrap(), "1");
+    }
+
+    #[test]
+    fn shift_rejects_out_of_range_amounts_instead_of_panicking() {
+        let mut calc = RPNParser::new();
+        let err = calc.parse("1 100 shl").unwrap_err();
+        assert_eq!(err, CalcError::InvalidShiftAmount(100));
+
+        let mut calc = RPNParser::new();
+        let err = calc.parse("5 -1 shr").unwrap_err();
+        assert_eq!(err, CalcError::InvalidShiftAmount(-1));
+    }
+
+    #[test]
+    fn constants_and_unary_functions() {
+        let mut calc = RPNParser::new();
+        calc.parse("pi 2 / sin").unwrap();
+        assert_eq!(calc.peek().unwrap(), "1");
+
+        let mut calc = RPNParser::new();
+        calc.parse("-9 abs").unwrap();
+        assert_eq!(calc.peek().unwrap(), "9");
+
+        let mut calc = RPNParser::new();
+        calc.parse("16 sqrt").unwrap();
+        assert_eq!(calc.peek().unwrap(), "4");
+    }
+
+    #[test]
+    fn factorial_is_distinct_from_variable_store() {
+        let mut calc = RPNParser::new();
+        calc.parse("5 !").unwrap();
+        assert_eq!(calc.peek().unwrap(), "120");
+
+        let mut calc = RPNParser::new();
+        calc.parse("10 !temp").unwrap();
+        assert_eq!(calc.peek().unwrap(), "10");
+        assert_eq!(calc.vars.get("temp").unwrap(), "10");
+    }
+
+    #[test]
+    fn factorial_reports_overflow_instead_of_panicking() {
+        let mut calc = RPNParser::new();
+        let err = calc.parse("25 !").unwrap_err();
+        assert_eq!(err, CalcError::Overflow);
+    }
+
+    #[test]
+    fn stack_manipulation_words() {
+        let mut calc = RPNParser::new();
+        calc.parse("10 2 dup").unwrap();
+        assert_eq!(calc.stack, vec!["10", "2", "2"]);
+
+        let mut calc = RPNParser::new();
+        calc.parse("10 2 drop").unwrap();
+        assert_eq!(calc.stack, vec!["10"]);
+
+        let mut calc = RPNParser::new();
+        calc.parse("10 2 over").unwrap();
+        assert_eq!(calc.stack, vec!["10", "2", "10"]);
+
+        let mut calc = RPNParser::new();
+        calc.parse("1 2 3 rot").unwrap();
+        assert_eq!(calc.stack, vec!["2", "3", "1"]);
+    }
+
+    #[test]
+    fn stack_manipulation_words_error_on_empty_stack() {
+        let mut calc = RPNParser::new();
+        calc.dup().unwrap_err();
+        calc.drop().unwrap_err();
+        calc.over().unwrap_err();
+        calc.rot().unwrap_err();
+    }
+
+    #[test]
+    fn divide_by_zero_errors() {
+        let mut calc = RPNParser::new();
+        calc.parse("5 0 /").unwrap_err();
+    }
+
+    #[test]
+    fn unknown_operator_errors() {
+        let mut calc = RPNParser::new();
+        let err = calc.parse("5 foo +").unwrap_err();
+        assert_eq!(err, CalcError::UnknownOperator("foo".to_string()));
+    }
+
+    #[test]
+    fn a_single_bare_number_is_valid_input() {
+        let mut calc = RPNParser::new();
+        calc.parse("10").unwrap();
+        assert_eq!(calc.peek().unwrap(), "10");
+    }
+
+    #[test]
+    fn a_trailing_bare_number_in_a_longer_expression_errors() {
+        let mut calc = RPNParser::new();
+        let err = calc.parse("5 2").unwrap_err();
+        assert_eq!(err, CalcError::TrailingOperatorExpected);
+    }
+
+    #[test]
+    fn infix_leading_unary_minus() {
+        let mut calc = RPNParser::new();
+        calc.parse_infix("-3 + 5").unwrap();
+        assert_eq!(calc.peek().unwrap(), "2");
+    }
+
+    #[test]
+    fn infix_unary_minus_after_operator() {
+        let mut calc = RPNParser::new();
+        calc.parse_infix("3 + -4").unwrap();
+        assert_eq!(calc.peek().unwrap(), "-1");
+    }
+
+    #[test]
+    fn infix_unary_minus_before_parenthesized_group() {
+        let mut calc = RPNParser::new();
+        calc.parse_infix("-(2 + 3)").unwrap();
+        assert_eq!(calc.peek().unwrap(), "-5");
+    }
+
+    #[test]
+    fn infix_binary_minus_followed_by_unary_minus() {
+        let mut calc = RPNParser::new();
+        calc.parse_infix("5 - -3").unwrap();
+        assert_eq!(calc.peek().unwrap(), "8");
+    }
+
+    #[test]
+    fn infix_stacked_unary_minus_without_parens() {
+        let mut calc = RPNParser::new();
+        calc.parse_infix("- -3").unwrap();
+        assert_eq!(calc.peek().unwrap(), "3");
+
+        let mut calc = RPNParser::new();
+        calc.parse_infix("- - 3 + 1").unwrap();
+        assert_eq!(calc.peek().unwrap(), "4");
+    }
+
+    #[test]
+    fn infix_mismatched_parentheses_errors() {
+        let mut calc = RPNParser::new();
+        let err = calc.parse_infix("(1 + 2").unwrap_err();
+        assert_eq!(err, CalcError::MismatchedParentheses);
+
+        let mut calc = RPNParser::new();
+        let err = calc.parse_infix("1 + 2)").unwrap_err();
+        assert_eq!(err, CalcError::MismatchedParentheses);
+    }
+}
@@ -9,6 +9,7 @@
 use anyhow::Result;
 use clap::Parser;
 use rpn_calculator::RPNParser;
+use rustyline::DefaultEditor;
 
 #[derive(Parser)]
 #[command(name = "Reverse Polish Notation (RPN) Calculator",
@@ -18,16 +19,23 @@ struct Cli {
         short,
         long,
         help = "Reverse Polish Notation Equation",
-        conflicts_with = "test_info"
+        conflicts_with_all = ["test_info", "repl"]
     )]
     expression: Option<String>,
     #[arg(
         short,
         long,
         help = "Show some test info and exit.",
-        conflicts_with = "expression"
+        conflicts_with_all = ["expression", "repl"]
     )]
     test_info: bool,
+    #[arg(
+        short,
+        long,
+        help = "Start an interactive REPL.",
+        conflicts_with_all = ["expression", "test_info"]
+    )]
+    repl: bool,
 }
 
 fn main() -> Result<()> {
@@ -35,11 +43,55 @@ fn main() -> Result<()> {
     if args.test_info {
         dump_test_info()?
     }
+    if args.repl {
+        run_repl()?
+    }
     if let Some(v) = args.expression {
         let mut calc = RPNParser::new();
-        calc.parse(&v)?;
-        let result = calc.peek()?;
-        println!("{}", result)
+        match calc.parse(&v) {
+            Ok(()) => {
+                let result = calc.peek()?;
+                println!("{}", result)
+            }
+            Err(e) => {
+                eprintln!("Math Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Drops the user into an interactive desk-calculator prompt. Each line is
+/// fed to a long-lived `RPNParser`, so the stack and `vars` persist across
+/// lines, and the top of the stack is echoed back after every evaluation.
+fn run_repl() -> Result<()> {
+    let mut calc = RPNParser::new();
+    let mut rl = DefaultEditor::new()?;
+    println!("RPN Calculator REPL. Type `quit` to exit, `?` to dump the stack, `&` to dump vars.");
+    loop {
+        let line = match rl.readline("rpn> ") {
+            Ok(line) => line,
+            Err(rustyline::error::ReadlineError::Interrupted | rustyline::error::ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        rl.add_history_entry(line)?;
+        match line {
+            "quit" => break,
+            "clear" => calc.clear(),
+            _ => match calc.parse(line) {
+                Ok(()) => {
+                    if let Ok(top) = calc.peek() {
+                        println!("{}", top)
+                    }
+                }
+                Err(e) => eprintln!("Math Error: {}", e),
+            },
+        }
     }
     Ok(())
 }
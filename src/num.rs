@@ -0,0 +1,210 @@
+use crate::error::CalcError;
+use std::fmt;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Neg};
+
+/// A numeric stack value.
+///
+/// Stays an `Int` for as long as every operation that touches it is exact,
+/// and promotes to a `Float` the moment it isn't (e.g. `10 3 /` or any
+/// decimal literal). This is what lets `3 4 2 * 1 5 - 2 3 ^ ^ / +` come out
+/// as a fraction instead of being truncated like integer math would.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Num {
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    /// Parses a stack entry as a `Num`, preferring `Int` and falling back to `Float`.
+    pub fn parse(token: &str) -> Result<Self, CalcError> {
+        if let Ok(i) = token.parse::<i64>() {
+            Ok(Num::Int(i))
+        } else if let Ok(f) = token.parse::<f64>() {
+            Ok(Num::Float(f))
+        } else {
+            Err(CalcError::NotANumber(token.to_string()))
+        }
+    }
+
+    /// Widens the value to `f64`, for use in operations that can't stay integral.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Num::Int(v) => v as f64,
+            Num::Float(v) => v,
+        }
+    }
+
+    pub fn subtract(self, other: Num) -> Num {
+        match (self, other) {
+            (Num::Int(a), Num::Int(b)) => Num::Int(a - b),
+            _ => Num::Float(self.as_f64() - other.as_f64()),
+        }
+    }
+
+    pub fn multiply(self, other: Num) -> Num {
+        match (self, other) {
+            (Num::Int(a), Num::Int(b)) => Num::Int(a * b),
+            _ => Num::Float(self.as_f64() * other.as_f64()),
+        }
+    }
+
+    /// Divides `self` by `other`, staying an `Int` only when the division is exact.
+    pub fn divide(self, other: Num) -> Result<Num, CalcError> {
+        if other.as_f64() == 0.0 {
+            return Err(CalcError::DivideByZero);
+        }
+        match (self, other) {
+            (Num::Int(a), Num::Int(b)) if a % b == 0 => Ok(Num::Int(a / b)),
+            _ => Ok(Num::Float(self.as_f64() / other.as_f64())),
+        }
+    }
+
+    /// Raises `self` to the power of `other`, staying an `Int` for non-negative integer powers.
+    pub fn exponent(self, other: Num) -> Num {
+        match (self, other) {
+            (Num::Int(base), Num::Int(power)) if power >= 0 => {
+                Num::Int(base.pow(power as u32))
+            }
+            _ => Num::Float(self.as_f64().powf(other.as_f64())),
+        }
+    }
+
+    /// Narrows the value to `i64`, for operations (bitwise, modulo) that only make
+    /// sense on whole numbers. Fails if the value has a fractional part.
+    fn as_i64(self) -> Result<i64, CalcError> {
+        match self {
+            Num::Int(v) => Ok(v),
+            Num::Float(v) if v.fract() == 0.0 => Ok(v as i64),
+            Num::Float(_) => Err(CalcError::NotAnInteger(self.to_string())),
+        }
+    }
+
+    pub fn modulo(self, other: Num) -> Result<Num, CalcError> {
+        let other_i = other.as_i64()?;
+        if other_i == 0 {
+            return Err(CalcError::DivideByZero);
+        }
+        Ok(Num::Int(self.as_i64()? % other_i))
+    }
+
+    /// Integer floor-division: `self` divided by `other`, rounded towards negative infinity.
+    pub fn floor_divide(self, other: Num) -> Result<Num, CalcError> {
+        let other_i = other.as_i64()?;
+        if other_i == 0 {
+            return Err(CalcError::DivideByZero);
+        }
+        let self_i = self.as_i64()?;
+        Ok(Num::Int((self_i as f64 / other_i as f64).floor() as i64))
+    }
+
+    pub fn shift_left(self, other: Num) -> Result<Num, CalcError> {
+        let amount = other.as_i64()?;
+        if !(0..64).contains(&amount) {
+            return Err(CalcError::InvalidShiftAmount(amount));
+        }
+        Ok(Num::Int(self.as_i64()? << amount))
+    }
+
+    pub fn shift_right(self, other: Num) -> Result<Num, CalcError> {
+        let amount = other.as_i64()?;
+        if !(0..64).contains(&amount) {
+            return Err(CalcError::InvalidShiftAmount(amount));
+        }
+        Ok(Num::Int(self.as_i64()? >> amount))
+    }
+
+    pub fn sqrt(self) -> Num {
+        Num::Float(self.as_f64().sqrt())
+    }
+
+    pub fn abs(self) -> Num {
+        match self {
+            Num::Int(v) => Num::Int(v.abs()),
+            Num::Float(v) => Num::Float(v.abs()),
+        }
+    }
+
+    pub fn ln(self) -> Num {
+        Num::Float(self.as_f64().ln())
+    }
+
+    pub fn sin(self) -> Num {
+        Num::Float(self.as_f64().sin())
+    }
+
+    pub fn cos(self) -> Num {
+        Num::Float(self.as_f64().cos())
+    }
+
+    pub fn tan(self) -> Num {
+        Num::Float(self.as_f64().tan())
+    }
+
+    /// Computes `self!`. Only defined for non-negative whole numbers, and
+    /// fails with [`CalcError::Overflow`] rather than panicking once the
+    /// result no longer fits in an `i64`.
+    pub fn factorial(self) -> Result<Num, CalcError> {
+        let n = self.as_i64()?;
+        if n < 0 {
+            return Err(CalcError::NotAnInteger(self.to_string()));
+        }
+        let result = (2..=n).try_fold(1i64, |acc, i| acc.checked_mul(i));
+        Ok(Num::Int(result.ok_or(CalcError::Overflow)?))
+    }
+}
+
+impl Add for Num {
+    type Output = Num;
+
+    fn add(self, other: Num) -> Num {
+        match (self, other) {
+            (Num::Int(a), Num::Int(b)) => Num::Int(a + b),
+            _ => Num::Float(self.as_f64() + other.as_f64()),
+        }
+    }
+}
+
+impl BitAnd for Num {
+    type Output = Result<Num, CalcError>;
+
+    fn bitand(self, other: Num) -> Result<Num, CalcError> {
+        Ok(Num::Int(self.as_i64()? & other.as_i64()?))
+    }
+}
+
+impl BitOr for Num {
+    type Output = Result<Num, CalcError>;
+
+    fn bitor(self, other: Num) -> Result<Num, CalcError> {
+        Ok(Num::Int(self.as_i64()? | other.as_i64()?))
+    }
+}
+
+impl BitXor for Num {
+    type Output = Result<Num, CalcError>;
+
+    fn bitxor(self, other: Num) -> Result<Num, CalcError> {
+        Ok(Num::Int(self.as_i64()? ^ other.as_i64()?))
+    }
+}
+
+impl Neg for Num {
+    type Output = Num;
+
+    fn neg(self) -> Num {
+        match self {
+            Num::Int(v) => Num::Int(-v),
+            Num::Float(v) => Num::Float(-v),
+        }
+    }
+}
+
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Num::Int(v) => write!(f, "{}", v),
+            Num::Float(v) if v.fract() == 0.0 && v.is_finite() => write!(f, "{}", *v as i64),
+            Num::Float(v) => write!(f, "{}", v),
+        }
+    }
+}